@@ -3,7 +3,8 @@
 //! You can use this crate to encrypt and decrypt messages using the Playfair Cipher.
 //! Currently, this crate only supports the English alphabet.
 //! Please note that the Playfair Cipher does not support the letter J.
-//! The letter J is replaced with the letter I.
+//! By default the letter J is replaced with the letter I, but [`Playfair::with_mode`]
+//! can be used to drop Q instead via [`AlphabetMode`].
 //!
 //! Please see the [Playfair Cipher](https://en.wikipedia.org/wiki/Playfair_cipher) Wikipedia article for more information.
 //! # Examples
@@ -16,13 +17,73 @@
 
 use std::fmt;
 
-// ALPHABET is a string containing all the letters of the English alphabet except J.
-const ALPHABET: &str = "ABCDEFGHIKLMNOPQRSTUVWXYZ";
 // FILLER is a filler letter (eg:X) in the duplicate plaintext letters to separate and regroup them.
 const FILLER: char = 'X';
 // TABLE_SIZE is the size of the table. The Playfair cipher uses a 5 by 5 table containing a key word or phrase.
 const TABLE_SIZE: usize = 5;
 
+/// Controls how the 26-letter English alphabet is squeezed into the 25-cell table.
+///
+/// The Playfair cipher's table only has room for 25 letters, so one letter of the
+/// alphabet has to go. The two conventional ways to do this are merging `J` into `I`
+/// (what this crate has always done) or dropping `Q` entirely.
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
+pub enum AlphabetMode {
+    /// Replace every `J` with `I`, using the table `ABCDEFGHIKLMNOPQRSTUVWXYZ`.
+    MergeJIntoI,
+    /// Drop `Q` entirely, using the table `ABCDEFGHIJKLMNOPRSTUVWXYZ`.
+    OmitQ,
+}
+
+impl AlphabetMode {
+    // The 25-letter alphabet used to build the table under this mode.
+    fn alphabet(self) -> &'static str {
+        match self {
+            AlphabetMode::MergeJIntoI => "ABCDEFGHIKLMNOPQRSTUVWXYZ",
+            AlphabetMode::OmitQ => "ABCDEFGHIJKLMNOPRSTUVWXYZ",
+        }
+    }
+
+    // Squeeze text down to the letters this mode can represent.
+    fn normalize(self, text: &str) -> String {
+        match self {
+            AlphabetMode::MergeJIntoI => text.replace('J', "I"),
+            AlphabetMode::OmitQ => text.replace('Q', ""),
+        }
+    }
+}
+
+/// Errors returned by the fallible [`Playfair::try_encrypt`] and
+/// [`Playfair::try_decrypt`] methods.
+#[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
+pub enum PlayfairError {
+    /// The input contained a character that is not an ASCII letter (try_encrypt strips spaces
+    /// first; try_decrypt does not, so a space in ciphertext is reported here).
+    NonAlphabeticCharacter(char),
+    /// The ciphertext has an odd number of letters, so it cannot be split into digraphs.
+    OddLengthCiphertext,
+    /// The input contained a letter that is not present in the table for the configured [`AlphabetMode`].
+    LetterNotInTable(char),
+}
+
+impl fmt::Display for PlayfairError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayfairError::NonAlphabeticCharacter(c) => {
+                write!(f, "'{c}' is not an alphabetic character")
+            }
+            PlayfairError::OddLengthCiphertext => {
+                write!(f, "ciphertext has an odd number of letters")
+            }
+            PlayfairError::LetterNotInTable(c) => {
+                write!(f, "'{c}' is not in the table for this alphabet mode")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PlayfairError {}
+
 /// The Playfair cipher uses a 5 by 5 table containing a key word or phrase.
 ///
 /// 1. If the same letters appear in the plaintext in a group,
@@ -39,6 +100,10 @@ pub struct Playfair {
     key: String,
     // table is a 5 by 5 table containing a key word or phrase.
     table: [[char; TABLE_SIZE]; TABLE_SIZE],
+    // mode controls how J/Q are folded into the 25-letter table.
+    mode: AlphabetMode,
+    // filler is the letter used to pad doubled letters and odd-length input.
+    filler: char,
 }
 
 impl fmt::Display for Playfair {
@@ -65,9 +130,48 @@ impl Playfair {
     /// println!("{}", playfair);
     /// ```
     pub fn new(key: String) -> Self {
+        Self::with_mode(key, AlphabetMode::MergeJIntoI)
+    }
+
+    /// Create a new Playfair using the given [`AlphabetMode`] to decide how the
+    /// 26-letter alphabet is squeezed into the 5x5 table.
+    /// # Examples
+    /// ```
+    /// use playfair_cipher_rs::{AlphabetMode, Playfair};
+    /// let playfair = Playfair::with_mode("playfair example".to_string(), AlphabetMode::OmitQ);
+    /// println!("{}", playfair);
+    /// ```
+    pub fn with_mode(key: String, mode: AlphabetMode) -> Self {
+        Self::build(key, mode, None)
+    }
+
+    /// Create a new Playfair with a custom filler letter used to pad doubled
+    /// letters and odd-length input, instead of the default `X`. Pass `None`
+    /// to keep the default.
+    /// # Examples
+    /// ```
+    /// use playfair_cipher_rs::Playfair;
+    /// let playfair = Playfair::with_filler("playfair example".to_string(), Some('Z'));
+    /// println!("{}", playfair);
+    /// ```
+    /// # Panics
+    /// Panics if `filler` is not an alphabetic letter present in the table.
+    pub fn with_filler(key: String, filler: Option<char>) -> Self {
+        Self::build(key, AlphabetMode::MergeJIntoI, filler)
+    }
+
+    fn build(key: String, mode: AlphabetMode, filler: Option<char>) -> Self {
+        let filler = filler.unwrap_or(FILLER).to_ascii_uppercase();
+        let table = Self::create_table(key.clone(), mode);
+        assert!(
+            filler.is_ascii_alphabetic() && table.iter().flatten().any(|&c| c == filler),
+            "{filler} is not a valid filler letter for this table"
+        );
         Self {
-            key: key.clone(),
-            table: Self::create_table(key),
+            key,
+            table,
+            mode,
+            filler,
         }
     }
 
@@ -79,26 +183,92 @@ impl Playfair {
     /// let cipher_text = playfair.encrypt("Hide the gold in the tree stump".to_string());
     /// assert_eq!(cipher_text, "BMODZBXDNABEKUDMUIXMMOUVIF");
     /// ```
+    /// # Panics
+    /// Panics if `plain_text` contains a character that cannot be encrypted.
+    /// See [`Playfair::try_encrypt`] for a non-panicking alternative.
     pub fn encrypt(&self, plain_text: String) -> String {
-        let plain_text = plain_text.to_uppercase().replace(' ', "").replace('J', "I");
+        self.try_encrypt(plain_text)
+            .expect("invalid plaintext, see Playfair::try_encrypt for the reason")
+    }
+
+    /// Encrypt a plaintext, returning a [`PlayfairError`] instead of panicking
+    /// if the input cannot be encrypted (eg: it contains a non-alphabetic
+    /// character, or a letter that is not in the table). The filler letter
+    /// itself is accepted in the plaintext and is not rejected.
+    /// # Examples
+    /// ```
+    /// use playfair_cipher_rs::{Playfair, PlayfairError};
+    /// let playfair = Playfair::new("playfair example".to_string());
+    /// assert_eq!(
+    ///     playfair.try_encrypt("Hide the gold in the tree stump".to_string()),
+    ///     Ok("BMODZBXDNABEKUDMUIXMMOUVIF".to_string())
+    /// );
+    /// assert_eq!(
+    ///     playfair.try_encrypt("Hide 123".to_string()),
+    ///     Err(PlayfairError::NonAlphabeticCharacter('1'))
+    /// );
+    /// ```
+    pub fn try_encrypt(&self, plain_text: String) -> Result<String, PlayfairError> {
+        let plain_text = self
+            .mode
+            .normalize(&plain_text.to_uppercase().replace(' ', ""));
+        self.validate_letters(&plain_text)?;
         let mut cipher_text = String::new();
         let mut chars = plain_text.chars().peekable();
         while let Some(c1) = chars.next() {
             let c2 = match chars.peek() {
                 Some(&c) => c,
-                // If only one letter is available when grouping to the last group, the letter X is added.
-                None => FILLER,
+                // If only one letter is available when grouping to the last group, the filler is added
+                // (or the alternate separator, if that last letter is the filler itself).
+                None => self.separator_for(c1),
             };
             // If the same letters appear in the plaintext in a group,
-            // insert a filler letter (eg:X) in the duplicate plaintext letters to separate and regroup them.
+            // insert a filler letter in the duplicate plaintext letters to separate and regroup them.
             if c1 == c2 {
-                cipher_text.push_str(&self.encrypt_pair(c1, FILLER));
+                cipher_text.push_str(&self.encrypt_pair(c1, self.separator_for(c1)));
             } else {
                 cipher_text.push_str(&self.encrypt_pair(c1, c2));
                 chars.next();
             }
         }
-        cipher_text
+        Ok(cipher_text)
+    }
+
+    // Check that every character is an alphabetic letter present in the table.
+    // The filler letter is allowed here: separator_for already picks an
+    // alternate separator whenever the filler would collide with itself.
+    fn validate_letters(&self, text: &str) -> Result<(), PlayfairError> {
+        for c in text.chars() {
+            if !c.is_ascii_alphabetic() {
+                return Err(PlayfairError::NonAlphabeticCharacter(c));
+            }
+            if !self.table.iter().flatten().any(|&t| t == c) {
+                return Err(PlayfairError::LetterNotInTable(c));
+            }
+        }
+        Ok(())
+    }
+
+    /// Encrypt a plaintext and format the result as space-separated digraphs,
+    /// the canonical display form for this cipher (eg: `"BM OD ZB XD"`).
+    /// # Examples
+    /// ```
+    /// use playfair_cipher_rs::Playfair;
+    /// let playfair = Playfair::new("playfair example".to_string());
+    /// let cipher_text = playfair.encrypt_digraphs("Hide the gold in the tree stump".to_string());
+    /// assert_eq!(cipher_text, "BM OD ZB XD NA BE KU DM UI XM MO UV IF");
+    /// ```
+    pub fn encrypt_digraphs(&self, plain_text: String) -> String {
+        Self::into_digraphs(&self.encrypt(plain_text))
+    }
+
+    // Group a string of letters into space-separated pairs.
+    fn into_digraphs(text: &str) -> String {
+        text.as_bytes()
+            .chunks(2)
+            .map(|pair| std::str::from_utf8(pair).unwrap())
+            .collect::<Vec<_>>()
+            .join(" ")
     }
 
     /// Decrypt a ciphertext.
@@ -109,14 +279,89 @@ impl Playfair {
     /// let plain_text = playfair.decrypt("BMODZBXDNABEKUDMUIXMMOUVIF".to_string());
     /// assert_eq!(plain_text, "HIDETHEGOLDINTHETREXESTUMP");
     /// ```
+    /// # Panics
+    /// Panics if `cipher_text` is not valid ciphertext for this table.
+    /// See [`Playfair::try_decrypt`] for a non-panicking alternative.
     pub fn decrypt(&self, cipher_text: String) -> String {
+        self.try_decrypt(cipher_text)
+            .expect("invalid ciphertext, see Playfair::try_decrypt for the reason")
+    }
+
+    /// Decrypt a ciphertext, returning a [`PlayfairError`] instead of panicking
+    /// if the input cannot be decrypted (eg: it contains a non-alphabetic
+    /// character, or has an odd number of letters).
+    /// # Examples
+    /// ```
+    /// use playfair_cipher_rs::{Playfair, PlayfairError};
+    /// let playfair = Playfair::new("playfair example".to_string());
+    /// assert_eq!(
+    ///     playfair.try_decrypt("BMODZBXDNABEKUDMUIXMMOUVIF".to_string()),
+    ///     Ok("HIDETHEGOLDINTHETREXESTUMP".to_string())
+    /// );
+    /// assert_eq!(
+    ///     playfair.try_decrypt("BMO".to_string()),
+    ///     Err(PlayfairError::OddLengthCiphertext)
+    /// );
+    /// ```
+    pub fn try_decrypt(&self, cipher_text: String) -> Result<String, PlayfairError> {
+        if !cipher_text.chars().count().is_multiple_of(2) {
+            return Err(PlayfairError::OddLengthCiphertext);
+        }
+        self.validate_letters(&cipher_text)?;
         let mut plain_text = String::new();
         let mut chars = cipher_text.chars().peekable();
         while let Some(c1) = chars.next() {
             let c2 = chars.next().unwrap();
             plain_text.push_str(&self.decrypt_pair(c1, c2));
         }
-        plain_text
+        Ok(plain_text)
+    }
+
+    /// Decrypt a ciphertext formatted as digraphs, eg: `"BM OD ZB XD"`.
+    /// Accepts both the spaced and unspaced forms, since any whitespace is
+    /// stripped before pairing up letters.
+    /// # Examples
+    /// ```
+    /// use playfair_cipher_rs::Playfair;
+    /// let playfair = Playfair::new("playfair example".to_string());
+    /// let plain_text = playfair.decrypt_digraphs("BM OD ZB XD NA BE KU DM UI XM MO UV IF".to_string());
+    /// assert_eq!(plain_text, "HIDETHEGOLDINTHETREXESTUMP");
+    /// ```
+    pub fn decrypt_digraphs(&self, cipher_text: String) -> String {
+        self.decrypt(cipher_text.chars().filter(|c| !c.is_whitespace()).collect())
+    }
+
+    /// Decrypt a ciphertext and heuristically remove the filler letters that
+    /// [`Playfair::encrypt`] inserted while padding, eg: turning
+    /// `"...TREXESTUMP"` back into `"...TREESTUMP"`.
+    ///
+    /// This is a best-effort "massage" pass, not a true inverse: it cannot
+    /// distinguish an inserted filler from one that was genuinely part of
+    /// the original message, so it may occasionally strip a filler that belongs.
+    /// # Examples
+    /// ```
+    /// use playfair_cipher_rs::Playfair;
+    /// let playfair = Playfair::new("playfair example".to_string());
+    /// let plain_text = playfair.decrypt_clean("BMODZBXDNABEKUDMUIXMMOUVIF".to_string());
+    /// assert_eq!(plain_text, "HIDETHEGOLDINTHETREESTUMP");
+    /// ```
+    pub fn decrypt_clean(&self, cipher_text: String) -> String {
+        // decrypt() recovers exactly one 2-letter block per encrypt_pair() call, so
+        // the blocks here line up 1:1 with the blocks encrypt() produced: a block's
+        // second letter is taken to be synthesized (not real plaintext) whenever it equals the
+        // separator encrypt would have picked for the block's first letter, be it
+        // the plain filler or the alternate letter separator_for() falls back to
+        // when the first letter is itself the filler.
+        let chars: Vec<char> = self.decrypt(cipher_text).chars().collect();
+        let mut cleaned = String::with_capacity(chars.len());
+        for pair in chars.chunks(2) {
+            let c1 = pair[0];
+            cleaned.push(c1);
+            if pair.len() == 2 && pair[1] != self.separator_for(c1) {
+                cleaned.push(pair[1]);
+            }
+        }
+        cleaned
     }
 
     fn decrypt_pair(&self, c1: char, c2: char) -> String {
@@ -171,9 +416,9 @@ impl Playfair {
         }
     }
 
-    fn create_table(key: String) -> [[char; TABLE_SIZE]; TABLE_SIZE] {
-        // Make sure the key is uppercase and replace J with I, because we are using a 5 by 5 table.
-        let key = key.to_uppercase().replace(' ', "").replace('J', "I");
+    fn create_table(key: String, mode: AlphabetMode) -> [[char; TABLE_SIZE]; TABLE_SIZE] {
+        // Make sure the key is uppercase and folded down to the mode's 25-letter alphabet.
+        let key = mode.normalize(&key.to_uppercase().replace(' ', ""));
         let mut temp = vec![];
         // Fill the temp with the key.
         for c in key.chars() {
@@ -182,7 +427,7 @@ impl Playfair {
             }
         }
         // Fill the temp with the rest of the alphabet.
-        for c in ALPHABET.chars() {
+        for c in mode.alphabet().chars() {
             if !temp.contains(&c) {
                 temp.push(c);
             }
@@ -197,6 +442,27 @@ impl Playfair {
         table
     }
 
+    // Pick a letter to separate a pair of identical letters `c` from each other.
+    // Normally this is just the filler, but if `c` itself is the filler
+    // (eg: the doubled letters are "XX"), using the filler again would just
+    // produce another double, so fall back to another letter of the table.
+    fn separator_for(&self, c: char) -> char {
+        if self.filler != c {
+            return self.filler;
+        }
+        for candidate in ['Q', 'Z'] {
+            if candidate != c && self.table.iter().flatten().any(|&t| t == candidate) {
+                return candidate;
+            }
+        }
+        self.table
+            .iter()
+            .flatten()
+            .find(|&&t| t != c)
+            .copied()
+            .unwrap_or(c)
+    }
+
     fn get_index(&self, c: char) -> (usize, usize) {
         for i in 0..TABLE_SIZE {
             for j in 0..TABLE_SIZE {
@@ -234,7 +500,7 @@ mod tests {
 
     #[test]
     fn test_create_table() {
-        let table = Playfair::create_table(TEST_KEY.to_string());
+        let table = Playfair::create_table(TEST_KEY.to_string(), AlphabetMode::MergeJIntoI);
         assert_eq!(table[0][0], 'P');
         assert_eq!(table[0][1], 'L');
         assert_eq!(table[0][2], 'A');
@@ -322,6 +588,146 @@ mod tests {
         assert_eq!(playfair.decrypt_pair('E', 'I'), "RM");
     }
 
+    #[test]
+    fn test_omit_q_mode_drops_q_instead_of_merging_j() {
+        let table = Playfair::create_table(TEST_KEY.to_string(), AlphabetMode::OmitQ);
+        assert!(table.iter().flatten().all(|&c| c != 'Q'));
+        assert!(table.iter().flatten().any(|&c| c == 'J'));
+
+        let playfair = Playfair::with_mode(TEST_KEY.to_string(), AlphabetMode::OmitQ);
+        let cipher_text = playfair.encrypt("Jump".to_string());
+        let plain_text = playfair.decrypt(cipher_text);
+        assert_eq!(plain_text, "JUMP");
+    }
+
+    #[test]
+    fn test_with_filler_pads_with_custom_letter() {
+        let playfair = Playfair::with_filler(TEST_KEY.to_string(), Some('Q'));
+        // "BALLOON" has a doubled L, so the custom filler Q should split it
+        // instead of the default X: BA LQ LO ON.
+        let cipher_text = playfair.encrypt("BALLOON".to_string());
+        let plain_text = playfair.decrypt(cipher_text);
+        assert_eq!(plain_text, "BALQLOON");
+    }
+
+    #[test]
+    #[should_panic(expected = "not a valid filler letter")]
+    fn test_with_filler_rejects_non_alphabetic_filler() {
+        Playfair::with_filler(TEST_KEY.to_string(), Some('1'));
+    }
+
+    #[test]
+    fn test_encrypt_doubled_filler_letter_produces_expected_ciphertext() {
+        let playfair = Playfair::new(TEST_KEY.to_string());
+        // "BOXX" pairs up as BO, X|X: the doubled letter is the filler itself, so
+        // the second pair must use the alternate separator Q instead of X again.
+        assert_eq!(playfair.encrypt("BOXX".to_string()), "DKGWGW");
+    }
+
+    #[test]
+    fn test_encrypt_does_not_double_up_on_filler_letter() {
+        let playfair = Playfair::new(TEST_KEY.to_string());
+        // "BOXX" pairs up as BO, X|X: the doubled letter is the filler itself,
+        // so the separator must not also be X or the digraph grouping breaks.
+        let cipher_text = playfair.encrypt("BOXX".to_string());
+        let plain_text = playfair.decrypt(cipher_text);
+        assert_eq!(plain_text, "BOXQXQ");
+    }
+
+    #[test]
+    fn test_encrypt_pads_trailing_lone_filler_letter_with_alternate() {
+        let playfair = Playfair::new(TEST_KEY.to_string());
+        // "TAX" ends in a lone X, which is also the default filler, so the
+        // pad letter must be something other than X.
+        let cipher_text = playfair.encrypt("TAX".to_string());
+        let plain_text = playfair.decrypt(cipher_text);
+        assert_eq!(plain_text, "TAXQ");
+    }
+
+    #[test]
+    fn test_encrypt_digraphs() {
+        let playfair = Playfair::new(TEST_KEY.to_string());
+        assert_eq!(
+            playfair.encrypt_digraphs("Hide the gold in the tree stump".to_string()),
+            "BM OD ZB XD NA BE KU DM UI XM MO UV IF"
+        );
+    }
+
+    #[test]
+    fn test_decrypt_digraphs_accepts_spaced_and_unspaced_forms() {
+        let playfair = Playfair::new(TEST_KEY.to_string());
+        assert_eq!(
+            playfair.decrypt_digraphs("BM OD ZB XD NA BE KU DM UI XM MO UV IF".to_string()),
+            "HIDETHEGOLDINTHETREXESTUMP"
+        );
+        assert_eq!(
+            playfair.decrypt_digraphs("BMODZBXDNABEKUDMUIXMMOUVIF".to_string()),
+            "HIDETHEGOLDINTHETREXESTUMP"
+        );
+    }
+
+    #[test]
+    fn test_try_encrypt_rejects_non_alphabetic_character() {
+        let playfair = Playfair::new(TEST_KEY.to_string());
+        assert_eq!(
+            playfair.try_encrypt("Hide 123".to_string()),
+            Err(PlayfairError::NonAlphabeticCharacter('1'))
+        );
+    }
+
+    #[test]
+    fn test_try_encrypt_allows_filler_letter_in_plaintext() {
+        // separator_for already guarantees a distinct separator whenever the
+        // filler letter collides with itself, so a literal X is not ambiguous.
+        let playfair = Playfair::new(TEST_KEY.to_string());
+        let cipher_text = playfair.try_encrypt("TAXI".to_string()).unwrap();
+        assert_eq!(playfair.decrypt(cipher_text), "TAXI");
+    }
+
+    #[test]
+    fn test_try_decrypt_rejects_odd_length_ciphertext() {
+        let playfair = Playfair::new(TEST_KEY.to_string());
+        assert_eq!(
+            playfair.try_decrypt("BMO".to_string()),
+            Err(PlayfairError::OddLengthCiphertext)
+        );
+    }
+
+    #[test]
+    fn test_try_decrypt_rejects_non_alphabetic_character() {
+        let playfair = Playfair::new(TEST_KEY.to_string());
+        assert_eq!(
+            playfair.try_decrypt("B1".to_string()),
+            Err(PlayfairError::NonAlphabeticCharacter('1'))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid plaintext")]
+    fn test_encrypt_panics_on_invalid_plaintext() {
+        let playfair = Playfair::new(TEST_KEY.to_string());
+        playfair.encrypt("Hide 123".to_string());
+    }
+
+    #[test]
+    fn test_decrypt_clean_strips_inserted_filler() {
+        let playfair = Playfair::new(TEST_KEY.to_string());
+        assert_eq!(
+            playfair.decrypt_clean("BMODZBXDNABEKUDMUIXMMOUVIF".to_string()),
+            "HIDETHEGOLDINTHETREESTUMP"
+        );
+    }
+
+    #[test]
+    fn test_decrypt_clean_strips_alternate_separator_without_corrupting_real_letters() {
+        let playfair = Playfair::new(TEST_KEY.to_string());
+        // "BOXX" encrypts to blocks BO, X+Q, X+Q (Q is the alternate separator
+        // picked because the doubled letter is the filler itself). decrypt_clean
+        // must drop both synthesized Qs and keep both genuine Xs intact.
+        let cipher_text = playfair.encrypt("BOXX".to_string());
+        assert_eq!(playfair.decrypt_clean(cipher_text), "BOXX");
+    }
+
     #[test]
     fn test_decrypt() {
         let playfair = Playfair::new(TEST_KEY.to_string());